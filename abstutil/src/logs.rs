@@ -1,4 +1,31 @@
 use crate::Timer;
+use geom::Pt2D;
+
+// How serious a warning is. get() reports errors ahead of the rest; the other ways of draining a
+// Warn<T> keep the order the warnings were added in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+// A single diagnostic, optionally tied to a point on the map so tooling can plot where map-import
+// problems happen.
+pub struct Warning {
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Pt2D>,
+}
+
+impl Warning {
+    fn line(&self) -> String {
+        match self.location {
+            Some(pt) => format!("[{:?}] {} (at {})", self.severity, self.message, pt),
+            None => format!("[{:?}] {}", self.severity, self.message),
+        }
+    }
+}
 
 //
 // - If it doesn't make sense to plumb Timer to a library call, return Warn<T>.
@@ -9,7 +36,7 @@ use crate::Timer;
 //   out DrawIntersection for an example.
 pub struct Warn<T> {
     value: T,
-    warnings: Vec<String>,
+    warnings: Vec<Warning>,
 }
 
 impl<T> Warn<T> {
@@ -23,19 +50,53 @@ impl<T> Warn<T> {
     pub fn warn(value: T, warning: String) -> Warn<T> {
         Warn {
             value,
-            warnings: vec![warning],
+            warnings: vec![Warning {
+                severity: Severity::Warning,
+                message: warning,
+                location: None,
+            }],
+        }
+    }
+
+    // Like warn, but remembers where on the map the problem is.
+    pub fn warn_at(value: T, warning: String, location: Pt2D) -> Warn<T> {
+        Warn {
+            value,
+            warnings: vec![Warning {
+                severity: Severity::Warning,
+                message: warning,
+                location: Some(location),
+            }],
         }
     }
 
     pub fn warnings(value: T, warnings: Vec<String>) -> Warn<T> {
-        Warn { value, warnings }
+        Warn {
+            value,
+            warnings: warnings
+                .into_iter()
+                .map(|message| Warning {
+                    severity: Severity::Warning,
+                    message,
+                    location: None,
+                })
+                .collect(),
+        }
+    }
+
+    // Every warning at least as severe as the given level.
+    pub fn filter_by_severity(&self, severity: Severity) -> Vec<&Warning> {
+        self.warnings
+            .iter()
+            .filter(|w| w.severity >= severity)
+            .collect()
     }
 
     pub fn unwrap(self) -> T {
         if !self.warnings.is_empty() {
             println!("{} warnings:", self.warnings.len());
-            for line in self.warnings {
-                println!("{}", line);
+            for w in &self.warnings {
+                println!("{}", w.line());
             }
         }
         self.value
@@ -44,8 +105,8 @@ impl<T> Warn<T> {
     pub fn expect(self, context: String) -> T {
         if !self.warnings.is_empty() {
             println!("{} warnings ({}):", self.warnings.len(), context);
-            for line in self.warnings {
-                println!("{}", line);
+            for w in &self.warnings {
+                println!("{}", w.line());
             }
         }
         self.value
@@ -53,15 +114,20 @@ impl<T> Warn<T> {
 
     pub fn get(self, timer: &mut Timer) -> T {
         // TODO Context from the current Timer phase, caller
-        for line in self.warnings {
-            timer.warn(line);
+        // Surface errors first; they're likelier to be what the caller cares about.
+        let (errors, rest): (Vec<Warning>, Vec<Warning>) = self
+            .warnings
+            .into_iter()
+            .partition(|w| w.severity == Severity::Error);
+        for w in errors.into_iter().chain(rest) {
+            timer.warn(w.line());
         }
         self.value
     }
 
     pub fn with_context(self, timer: &mut Timer, context: String) -> T {
-        for line in self.warnings {
-            timer.warn(format!("{}: {}", context, line));
+        for w in self.warnings {
+            timer.warn(format!("{}: {}", context, w.line()));
         }
         self.value
     }
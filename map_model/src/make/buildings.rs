@@ -5,14 +5,39 @@ use crate::{
     ParkingLotID, Position,
 };
 use abstutil::Timer;
-use geom::{Distance, HashablePt2D, Line, PolyLine, Polygon};
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use geom::{Distance, FindClosest, GPSBounds, HashablePt2D, Line, PolyLine, Polygon};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::collections::{BTreeMap, HashSet};
 
+// How a building is used, for the purposes of placing home and work trips during scenario
+// generation. Occupant counts are rough estimates from floor area and OSM tags, not ground truth.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BuildingType {
+    Residential { num_residents: usize },
+    Commercial { num_workers: usize },
+    ResidentialCommercial { num_residents: usize, num_workers: usize },
+    Empty,
+}
+
+// A building or parking lot that couldn't be imported, kept around so the spatial distribution of
+// failures can be rendered instead of vanishing into the warning log.
+#[derive(Clone, Debug)]
+pub struct DiscardedGeometry {
+    pub polygon: Polygon,
+    pub osm_id: String,
+    pub reason: String,
+}
+
+// Returns the buildings that were successfully imported, plus anything discarded along the way
+// (no sidewalk or road nearby, zero-length front path) so callers can render the failures. Callers
+// that destructured the old Vec<Building>-only return need updating for the new tuple.
 pub fn make_all_buildings(
     input: &BTreeMap<OriginalBuilding, RawBuilding>,
     map: &Map,
     timer: &mut Timer,
-) -> Vec<Building> {
+) -> (Vec<Building>, Vec<DiscardedGeometry>) {
     timer.start("convert buildings");
     let mut center_per_bldg: BTreeMap<OriginalBuilding, HashablePt2D> = BTreeMap::new();
     let mut query: HashSet<HashablePt2D> = HashSet::new();
@@ -25,15 +50,25 @@ pub fn make_all_buildings(
     }
 
     // Skip buildings that're too far away from their sidewalk
+    let config = map.get_config();
     let sidewalk_pts = find_sidewalk_points(
         map.get_bounds(),
         query,
         map.all_lanes(),
-        Distance::meters(100.0),
+        config.bldg_sidewalk_radius,
         timer,
     );
 
+    // Buildings that miss the sidewalk radius fall back to attaching directly to the nearest road.
+    let mut closest_driving: FindClosest<LaneID> = FindClosest::new(map.get_bounds());
+    for l in map.all_lanes() {
+        if l.is_driving() {
+            closest_driving.add(l.id, l.lane_center_pts.points());
+        }
+    }
+
     let mut results = Vec::new();
+    let mut discarded = Vec::new();
     timer.start_iter("create building front paths", center_per_bldg.len());
     for (orig_id, bldg_center) in center_per_bldg {
         timer.next();
@@ -44,6 +79,11 @@ pub fn make_all_buildings(
                     "Skipping building {} because front path has 0 length",
                     orig_id
                 ));
+                discarded.push(DiscardedGeometry {
+                    polygon: input[&orig_id].polygon.clone(),
+                    osm_id: orig_id.to_string(),
+                    reason: "zero-length front path".to_string(),
+                });
                 continue;
             }
             let b = &input[&orig_id];
@@ -60,8 +100,10 @@ pub fn make_all_buildings(
                 front_path: FrontPath {
                     sidewalk: *sidewalk_pos,
                     line: sidewalk_line.clone(),
+                    road_attached: false,
                 },
                 amenities: b.amenities.clone(),
+                bldg_type: classify_building(b),
                 parking: None,
                 label_center: b.polygon.polylabel(),
             };
@@ -99,23 +141,115 @@ pub fn make_all_buildings(
             }
 
             results.push(bldg);
+        } else if let Some((driving_lane, driving_pt)) =
+            closest_driving.closest_pt(bldg_center.to_pt2d(), config.bldg_road_fallback_radius)
+        {
+            // No sidewalk nearby, but there's a road within the fallback radius. Attach the
+            // building straight to the road edge instead of dropping it.
+            let b = &input[&orig_id];
+            if bldg_center.to_pt2d() == driving_pt {
+                timer.warn(format!(
+                    "Skipping building {} because front path has 0 length",
+                    orig_id
+                ));
+                discarded.push(DiscardedGeometry {
+                    polygon: b.polygon.clone(),
+                    osm_id: orig_id.to_string(),
+                    reason: "zero-length front path".to_string(),
+                });
+                continue;
+            }
+            let front_line = trim_path(&b.polygon, Line::new(bldg_center.to_pt2d(), driving_pt));
+            if front_line.length() == Distance::ZERO {
+                discarded.push(DiscardedGeometry {
+                    polygon: b.polygon.clone(),
+                    osm_id: orig_id.to_string(),
+                    reason: "zero-length front path".to_string(),
+                });
+                continue;
+            }
+            let dist_along = match map
+                .get_l(driving_lane)
+                .lane_center_pts
+                .dist_along_of_point(driving_pt)
+                .map(|(dist, _)| dist)
+            {
+                Some(dist) => dist,
+                None => {
+                    discarded.push(DiscardedGeometry {
+                        polygon: b.polygon.clone(),
+                        osm_id: orig_id.to_string(),
+                        reason: "couldn't project onto the fallback road".to_string(),
+                    });
+                    continue;
+                }
+            };
+            let driving_pos = Position::new(driving_lane, dist_along);
+
+            // Same buffer as the sidewalk path: don't plant a driveway right at a lane's end.
+            let buffer = Distance::meters(7.0);
+            let parking = if driving_pos.dist_along() > buffer
+                && map.get_l(driving_lane).length() - driving_pos.dist_along() > buffer
+            {
+                let driveway_line =
+                    PolyLine::new(vec![front_line.pt1(), front_line.pt2(), driving_pos.pt(map)]);
+                Some(OffstreetParking {
+                    public_garage_name: b.public_garage_name.clone(),
+                    num_spots: b.num_parking_spots,
+                    driveway_line,
+                    driving_pos,
+                })
+            } else {
+                None
+            };
+            let id = BuildingID(results.len());
+            if parking.is_none() {
+                timer.warn(format!(
+                    "{} can't have a driveway. Forfeiting {} parking spots",
+                    id, b.num_parking_spots
+                ));
+            }
+            results.push(Building {
+                id,
+                polygon: b.polygon.clone(),
+                address: get_address(&b.osm_tags, driving_lane, map),
+                name: b.osm_tags.get(osm::NAME).cloned(),
+                osm_way_id: orig_id.osm_way_id,
+                front_path: FrontPath {
+                    sidewalk: driving_pos,
+                    line: front_line,
+                    road_attached: true,
+                },
+                amenities: b.amenities.clone(),
+                bldg_type: classify_building(b),
+                parking,
+                label_center: b.polygon.polylabel(),
+            });
+        } else {
+            discarded.push(DiscardedGeometry {
+                polygon: input[&orig_id].polygon.clone(),
+                osm_id: orig_id.to_string(),
+                reason: "no sidewalk or road within range".to_string(),
+            });
         }
     }
 
     timer.note(format!(
         "Discarded {} buildings that weren't close enough to a sidewalk",
-        input.len() - results.len()
+        discarded.len()
     ));
     timer.stop("convert buildings");
 
-    results
+    (results, discarded)
 }
 
+// Same deal as make_all_buildings: returns the imported lots plus anything discarded, so callers
+// need updating for the new tuple return.
 pub fn make_all_parking_lots(
     input: &Vec<RawParkingLot>,
     map: &Map,
     timer: &mut Timer,
-) -> Vec<ParkingLot> {
+) -> (Vec<ParkingLot>, Vec<DiscardedGeometry>) {
     timer.start("convert parking lots");
     let mut center_per_lot: Vec<HashablePt2D> = Vec::new();
     let mut query: HashSet<HashablePt2D> = HashSet::new();
@@ -134,6 +268,7 @@ pub fn make_all_parking_lots(
     );
 
     let mut results = Vec::new();
+    let mut discarded = Vec::new();
     timer.start_iter("create parking lot driveways", center_per_lot.len());
     for (lot_center, orig) in center_per_lot.into_iter().zip(input.iter()) {
         timer.next();
@@ -145,6 +280,11 @@ pub fn make_all_parking_lots(
                     "Skipping parking lot {} because driveway has 0 length",
                     orig.osm_id
                 ));
+                discarded.push(DiscardedGeometry {
+                    polygon: orig.polygon.clone(),
+                    osm_id: orig.osm_id.to_string(),
+                    reason: "zero-length front path".to_string(),
+                });
                 continue;
             }
             let sidewalk_line =
@@ -195,17 +335,157 @@ pub fn make_all_parking_lots(
                      spots",
                     orig.osm_id, orig.capacity
                 ));
+                discarded.push(DiscardedGeometry {
+                    polygon: orig.polygon.clone(),
+                    osm_id: orig.osm_id.to_string(),
+                    reason: "no adjacent driving lane".to_string(),
+                });
             }
+        } else {
+            discarded.push(DiscardedGeometry {
+                polygon: orig.polygon.clone(),
+                osm_id: orig.osm_id.to_string(),
+                reason: "no sidewalk within 500m".to_string(),
+            });
         }
     }
 
     timer.note(format!(
         "Discarded {} parking lots that weren't close enough to a sidewalk",
-        input.len() - results.len()
+        discarded.len()
     ));
     timer.stop("convert parking lots");
 
-    results
+    (results, discarded)
+}
+
+impl Map {
+    // Dump every building's polygon, front path, and driveway as a GeoJSON FeatureCollection so
+    // the import can be eyeballed in QGIS or a browser map without spinning up the simulator.
+    pub fn buildings_to_geojson(&self) -> String {
+        let gps_bounds = self.get_gps_bounds();
+        let mut features = Vec::new();
+        for b in self.all_buildings() {
+            let mut props = JsonMap::new();
+            props.insert("type".to_string(), JsonValue::from("building"));
+            props.insert("address".to_string(), JsonValue::from(b.address.clone()));
+            props.insert("osm_way_id".to_string(), JsonValue::from(b.osm_way_id));
+            features.push(polygon_feature(&b.polygon, gps_bounds, props));
+
+            let mut props = JsonMap::new();
+            props.insert("type".to_string(), JsonValue::from("front_path"));
+            props.insert("address".to_string(), JsonValue::from(b.address.clone()));
+            features.push(line_feature(&b.front_path.line, gps_bounds, props));
+
+            if let Some(ref p) = b.parking {
+                let mut props = JsonMap::new();
+                props.insert("type".to_string(), JsonValue::from("driveway"));
+                props.insert("num_spots".to_string(), JsonValue::from(p.num_spots));
+                features.push(polyline_feature(&p.driveway_line, gps_bounds, props));
+            }
+        }
+        collection_to_string(features)
+    }
+
+    // Same as buildings_to_geojson, but for parking lots and their driveways.
+    pub fn parking_lots_to_geojson(&self) -> String {
+        let gps_bounds = self.get_gps_bounds();
+        let mut features = Vec::new();
+        for pl in self.all_parking_lots() {
+            let mut props = JsonMap::new();
+            props.insert("type".to_string(), JsonValue::from("parking_lot"));
+            props.insert("osm_id".to_string(), JsonValue::from(pl.osm_id));
+            props.insert("num_parking_spots".to_string(), JsonValue::from(pl.capacity));
+            features.push(polygon_feature(&pl.polygon, gps_bounds, props));
+
+            let mut props = JsonMap::new();
+            props.insert("type".to_string(), JsonValue::from("driveway"));
+            props.insert("osm_id".to_string(), JsonValue::from(pl.osm_id));
+            features.push(polyline_feature(&pl.driveway_line, gps_bounds, props));
+        }
+        collection_to_string(features)
+    }
+}
+
+fn polygon_feature(
+    polygon: &Polygon,
+    gps_bounds: &GPSBounds,
+    props: JsonMap<String, JsonValue>,
+) -> Feature {
+    let mut ring = Vec::new();
+    for pt in gps_bounds.convert_back(polygon.points()) {
+        ring.push(vec![pt.x(), pt.y()]);
+    }
+    // GeoJSON requires a closed linear ring (first position repeated as the last); Polygon::points
+    // doesn't guarantee that, so close it explicitly.
+    if ring.first() != ring.last() {
+        ring.push(ring[0].clone());
+    }
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::Polygon(vec![ring]))),
+        id: None,
+        properties: Some(props),
+        foreign_members: None,
+    }
+}
+
+fn line_feature(
+    line: &Line,
+    gps_bounds: &GPSBounds,
+    props: JsonMap<String, JsonValue>,
+) -> Feature {
+    let pts = gps_bounds.convert_back(&vec![line.pt1(), line.pt2()]);
+    let coords = pts.into_iter().map(|pt| vec![pt.x(), pt.y()]).collect();
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::LineString(coords))),
+        id: None,
+        properties: Some(props),
+        foreign_members: None,
+    }
+}
+
+fn polyline_feature(
+    pl: &PolyLine,
+    gps_bounds: &GPSBounds,
+    props: JsonMap<String, JsonValue>,
+) -> Feature {
+    let coords = gps_bounds
+        .convert_back(pl.points())
+        .into_iter()
+        .map(|pt| vec![pt.x(), pt.y()])
+        .collect();
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::LineString(coords))),
+        id: None,
+        properties: Some(props),
+        foreign_members: None,
+    }
+}
+
+// Dump the geometry of buildings and parking lots that couldn't be imported, tagged with why, so
+// map authors can spot systematic problems (a whole neighborhood with no sidewalks) on a map.
+pub fn discarded_to_geojson(discarded: &[DiscardedGeometry], gps_bounds: &GPSBounds) -> String {
+    let mut features = Vec::new();
+    for d in discarded {
+        let mut props = JsonMap::new();
+        props.insert("type".to_string(), JsonValue::from("discarded"));
+        props.insert("osm_id".to_string(), JsonValue::from(d.osm_id.clone()));
+        props.insert("reason".to_string(), JsonValue::from(d.reason.clone()));
+        features.push(polygon_feature(&d.polygon, gps_bounds, props));
+    }
+    collection_to_string(features)
+}
+
+fn collection_to_string(features: Vec<Feature>) -> String {
+    let collection = FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    GeoJson::from(collection).to_string()
 }
 
 // Adjust the path to start on the building's border, not center
@@ -222,6 +502,43 @@ fn trim_path(poly: &Polygon, path: Line) -> Line {
     path
 }
 
+// Guess whether a building holds residents, workers, or both from its OSM tags and amenities, and
+// estimate how many from the footprint area. The floor-area numbers are deliberately crude; they
+// just need to be proportional so scenario generation spreads trips sensibly.
+fn classify_building(b: &RawBuilding) -> BuildingType {
+    let area = b.polygon.area();
+
+    let is_residential = matches!(
+        b.osm_tags.get(osm::BUILDING).map(|x| x.as_str()),
+        Some("house")
+            | Some("detached")
+            | Some("residential")
+            | Some("apartments")
+            | Some("terrace")
+            | Some("dormitory")
+            | Some("bungalow")
+    );
+    let is_commercial = b.osm_tags.contains_key(osm::SHOP)
+        || b.osm_tags.contains_key(osm::OFFICE)
+        || b.osm_tags.contains_key(osm::AMENITY)
+        || !b.amenities.is_empty();
+
+    // Roughly one resident per 100 square meters, one worker per 50, with at least one occupant if
+    // the building is used at all.
+    let num_residents = ((area / 100.0) as usize).max(1);
+    let num_workers = ((area / 50.0) as usize).max(1);
+
+    match (is_residential, is_commercial) {
+        (true, true) => BuildingType::ResidentialCommercial {
+            num_residents,
+            num_workers,
+        },
+        (true, false) => BuildingType::Residential { num_residents },
+        (false, true) => BuildingType::Commercial { num_workers },
+        (false, false) => BuildingType::Empty,
+    }
+}
+
 fn get_address(tags: &BTreeMap<String, String>, sidewalk: LaneID, map: &Map) -> String {
     match (tags.get("addr:housenumber"), tags.get("addr:street")) {
         (Some(num), Some(st)) => format!("{} {}", num, st),
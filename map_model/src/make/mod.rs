@@ -0,0 +1,31 @@
+pub mod buildings;
+
+use std::collections::BTreeMap;
+
+use abstutil::Timer;
+
+use crate::make::buildings::{make_all_buildings, make_all_parking_lots};
+use crate::raw::{OriginalBuilding, RawBuilding, RawParkingLot};
+use crate::Map;
+
+impl Map {
+    // The only caller of make_all_buildings/make_all_parking_lots. Both now return
+    // (imported, discarded) tuples instead of a bare Vec, so this destructures both halves:
+    // the imported geometry populates the map as before, and the discarded geometry is kept
+    // around so buildings_to_geojson's sibling (discarded_to_geojson) has something to draw.
+    pub(crate) fn import_buildings_and_parking_lots(
+        &mut self,
+        raw_buildings: &BTreeMap<OriginalBuilding, RawBuilding>,
+        raw_parking_lots: &Vec<RawParkingLot>,
+        timer: &mut Timer,
+    ) {
+        let (buildings, discarded_buildings) = make_all_buildings(raw_buildings, self, timer);
+        let (parking_lots, discarded_parking_lots) =
+            make_all_parking_lots(raw_parking_lots, self, timer);
+
+        self.buildings = buildings;
+        self.parking_lots = parking_lots;
+        self.discarded_buildings = discarded_buildings;
+        self.discarded_parking_lots = discarded_parking_lots;
+    }
+}
@@ -0,0 +1,23 @@
+use geom::Distance;
+use serde::{Deserialize, Serialize};
+
+// Import-time knobs for building Map from raw OSM data. Only the fields this request touches are
+// reconstructed here; the rest of MapConfig isn't part of this snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapConfig {
+    // How far a building's center can be from a sidewalk before it's considered for the
+    // road-fallback pass instead.
+    pub bldg_sidewalk_radius: Distance,
+    // How far a building missing the sidewalk radius can be from a driving lane before it's
+    // discarded outright instead of getting a road-attached front path.
+    pub bldg_road_fallback_radius: Distance,
+}
+
+impl Default for MapConfig {
+    fn default() -> MapConfig {
+        MapConfig {
+            bldg_sidewalk_radius: Distance::meters(100.0),
+            bldg_road_fallback_radius: Distance::meters(500.0),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use geom::{Line, PolyLine, Polygon, Pt2D};
+use serde::{Deserialize, Serialize};
+
+use crate::Position;
+
+// Building carries a BuildingType; re-export it here too so `use crate::objects::building::*`
+// (and the crate-root re-export alongside it) pulls in both together.
+pub use crate::make::buildings::BuildingType;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BuildingID(pub usize);
+
+impl fmt::Display for BuildingID {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Building #{}", self.0)
+    }
+}
+
+// A single OSM-derived amenity (shop, cafe, etc) embedded in a building's footprint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Amenity {
+    pub names: String,
+    pub amenity_type: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Building {
+    pub id: BuildingID,
+    pub polygon: Polygon,
+    pub address: String,
+    pub name: Option<String>,
+    pub osm_way_id: i64,
+    pub front_path: FrontPath,
+    pub amenities: Vec<Amenity>,
+    // How this building's residents/workers are estimated for scenario generation.
+    pub bldg_type: BuildingType,
+    pub parking: Option<OffstreetParking>,
+    pub label_center: Pt2D,
+}
+
+// How a building's front path reaches the road network.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrontPath {
+    pub sidewalk: Position,
+    pub line: Line,
+    // True if this building missed the sidewalk search radius and was instead wired straight to
+    // the nearest driving lane by the road-fallback pass, rather than to an actual sidewalk.
+    pub road_attached: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OffstreetParking {
+    pub public_garage_name: Option<String>,
+    pub num_spots: usize,
+    pub driveway_line: PolyLine,
+    pub driving_pos: Position,
+}